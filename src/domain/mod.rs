@@ -0,0 +1,5 @@
+mod short_code_alias;
+mod validated_url;
+
+pub use short_code_alias::ShortCodeAlias;
+pub use validated_url::ValidatedUrl;
@@ -0,0 +1,59 @@
+const MIN_LENGTH: usize = 3;
+const MAX_LENGTH: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct ShortCodeAlias(String);
+
+impl ShortCodeAlias {
+    /// Parses `s` as a custom alias, requiring 3-32 characters drawn from
+    /// `[A-Za-z0-9_-]` so it is safe to use directly as a `short_code`.
+    pub fn parse(s: &str) -> Result<ShortCodeAlias, String> {
+        let len = s.chars().count();
+        if len < MIN_LENGTH || len > MAX_LENGTH {
+            return Err(format!(
+                "custom_alias must be between {} and {} characters long",
+                MIN_LENGTH, MAX_LENGTH
+            ));
+        }
+
+        let is_valid = s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_valid {
+            return Err("custom_alias may only contain letters, digits, '_' and '-'".to_string());
+        }
+
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl AsRef<str> for ShortCodeAlias {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShortCodeAlias;
+
+    #[test]
+    fn alias_shorter_than_three_characters_is_rejected() {
+        assert!(ShortCodeAlias::parse("ab").is_err());
+    }
+
+    #[test]
+    fn alias_longer_than_32_characters_is_rejected() {
+        assert!(ShortCodeAlias::parse(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn alias_with_invalid_characters_is_rejected() {
+        assert!(ShortCodeAlias::parse("not valid!").is_err());
+    }
+
+    #[test]
+    fn valid_alias_is_accepted() {
+        assert!(ShortCodeAlias::parse("my-cool_alias123").is_ok());
+    }
+}
@@ -0,0 +1,93 @@
+use url::Url;
+
+const MAX_URL_LENGTH: usize = 2048;
+
+#[derive(Debug, Clone)]
+pub struct ValidatedUrl(String);
+
+impl ValidatedUrl {
+    /// Parses `s` as a URL, rejecting anything that isn't a well-formed
+    /// `http`/`https` link with a host, is too long, or points back at
+    /// `domain` (which would turn the redirect into a loop).
+    pub fn parse(s: &str, domain: &str) -> Result<ValidatedUrl, String> {
+        if s.is_empty() {
+            return Err("URL cannot be empty".to_string());
+        }
+        if s.chars().count() > MAX_URL_LENGTH {
+            return Err(format!(
+                "URL must not be longer than {} characters",
+                MAX_URL_LENGTH
+            ));
+        }
+
+        let parsed = Url::parse(s).map_err(|_| "URL is not a valid URL".to_string())?;
+
+        if parsed.scheme() != "http" && parsed.scheme() != "https" {
+            return Err("URL must start with http:// or https://".to_string());
+        }
+
+        let host = parsed
+            .host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| "URL must have a host".to_string())?;
+
+        if host.eq_ignore_ascii_case(domain) {
+            return Err("URL must not point back at this shortener".to_string());
+        }
+
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl AsRef<str> for ValidatedUrl {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidatedUrl;
+
+    #[test]
+    fn empty_url_is_rejected() {
+        assert!(ValidatedUrl::parse("", "short.ly").is_err());
+    }
+
+    #[test]
+    fn url_without_scheme_is_rejected() {
+        assert!(ValidatedUrl::parse("www.example.com", "short.ly").is_err());
+    }
+
+    #[test]
+    fn ftp_url_is_rejected() {
+        assert!(ValidatedUrl::parse("ftp://example.com/file", "short.ly").is_err());
+    }
+
+    #[test]
+    fn url_without_host_is_rejected() {
+        assert!(ValidatedUrl::parse("https://", "short.ly").is_err());
+    }
+
+    #[test]
+    fn url_pointing_at_the_shortener_itself_is_rejected() {
+        assert!(ValidatedUrl::parse("https://short.ly/abc", "short.ly").is_err());
+    }
+
+    #[test]
+    fn overly_long_url_is_rejected() {
+        let long_path = "a".repeat(2048);
+        let url = format!("https://example.com/{}", long_path);
+        assert!(ValidatedUrl::parse(&url, "short.ly").is_err());
+    }
+
+    #[test]
+    fn valid_http_url_is_accepted() {
+        assert!(ValidatedUrl::parse("http://example.com/some/path", "short.ly").is_ok());
+    }
+
+    #[test]
+    fn valid_https_url_is_accepted() {
+        assert!(ValidatedUrl::parse("https://example.com/some/path", "short.ly").is_ok());
+    }
+}
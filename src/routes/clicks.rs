@@ -0,0 +1,88 @@
+use actix_web::{HttpResponse, web};
+use serde::Serialize;
+use sqlx::PgPool;
+
+#[derive(Serialize)]
+struct DailyClicks {
+    date: chrono::NaiveDate,
+    hits: i64,
+}
+
+#[derive(Serialize)]
+struct ShortUrlStats {
+    short_code: String,
+    total_hits: i64,
+    daily: Vec<DailyClicks>,
+}
+
+#[tracing::instrument(
+    name = "Fetching click stats",
+    skip(connection_pool),
+    fields(short_code = %path)
+)]
+pub async fn get_short_url_stats(
+    path: web::Path<String>,
+    connection_pool: web::Data<PgPool>,
+) -> HttpResponse {
+    let short_code = path.into_inner();
+
+    match sqlx::query!(
+        r#"
+        SELECT short_code FROM short_urls WHERE short_code = $1
+        "#,
+        short_code
+    )
+    .fetch_optional(connection_pool.get_ref())
+    .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            tracing::error!("Failed to execute query: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    let total_hits = match sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!" FROM clicks WHERE short_code = $1
+        "#,
+        short_code
+    )
+    .fetch_one(connection_pool.get_ref())
+    .await
+    {
+        Ok(record) => record.count,
+        Err(e) => {
+            tracing::error!("Failed to execute query: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let daily = match sqlx::query_as!(
+        DailyClicks,
+        r#"
+        SELECT date_trunc('day', clicked_at)::date as "date!", COUNT(*) as "hits!"
+        FROM clicks
+        WHERE short_code = $1
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+        short_code
+    )
+    .fetch_all(connection_pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute query: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    HttpResponse::Ok().json(ShortUrlStats {
+        short_code,
+        total_hits,
+        daily,
+    })
+}
@@ -0,0 +1,7 @@
+mod clicks;
+mod health_check;
+mod short_urls;
+
+pub use clicks::*;
+pub use health_check::*;
+pub use short_urls::*;
@@ -1,88 +1,232 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, http::header, web};
 use chrono::Utc;
 use sqlx::PgPool;
 
 use crate::configuration::Settings;
+use crate::domain::{ShortCodeAlias, ValidatedUrl};
 
 #[derive(serde::Deserialize, Debug)]
 pub struct GenerateShortUrlRequest {
     url: String,
+    custom_alias: Option<String>,
 }
 
+enum InsertShortUrlError {
+    AliasTaken,
+    ExhaustedRetries,
+    Database(sqlx::Error),
+}
+
+#[tracing::instrument(
+    name = "Generating a short URL",
+    skip(body, connection_pool, settings),
+    fields(url = %body.url)
+)]
 pub async fn generate_short_url(
     body: web::Json<GenerateShortUrlRequest>,
     connection_pool: web::Data<PgPool>,
     settings: web::Data<Settings>,
 ) -> HttpResponse {
-    println!("Received URL to shorten: {}", body.url);
-    if body.url.is_empty() {
-        return HttpResponse::BadRequest().body("URL cannot be empty");
-    }
-    if !body.url.starts_with("http://") && !body.url.starts_with("https://") {
-        return HttpResponse::BadRequest().body("URL must start with http:// or https://");
-    }
+    let url = match ValidatedUrl::parse(&body.url, &settings.domain) {
+        Ok(url) => url,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let custom_alias = match body.custom_alias.as_deref().map(ShortCodeAlias::parse) {
+        Some(Ok(alias)) => Some(alias),
+        Some(Err(e)) => return HttpResponse::BadRequest().body(e),
+        None => None,
+    };
 
     let is_url_present = sqlx::query!(
         r#"
         SELECT short_code FROM short_urls WHERE original_url = $1
         "#,
-        &body.url
+        url.as_ref()
     )
     .fetch_one(connection_pool.get_ref())
     .await;
 
     if let Ok(record) = is_url_present {
+        tracing::info!("URL was already shortened, reusing existing short code.");
         return HttpResponse::Ok().json(
-            serde_json::json!({ "short_code": format!("{}/{}", settings.domain, record.short_code) }),
+            serde_json::json!({ "short_url": format!("{}/{}", settings.domain, record.short_code) }),
         );
     }
 
-    // insert the URL into the database and generate a short code
-    let short_code = generate_short_code(&connection_pool).await;
+    match insert_short_url(connection_pool.get_ref(), &url, custom_alias.as_ref()).await {
+        Ok(short_code) => {
+            tracing::info!(short_code = %short_code, "Shortened URL and saved it to the database.");
+            HttpResponse::Ok().json(
+                serde_json::json!({ "short_url": format!("{}/{}", settings.domain, short_code) }),
+            )
+        }
+        Err(InsertShortUrlError::AliasTaken) => {
+            HttpResponse::Conflict().body("custom_alias is already taken")
+        }
+        Err(InsertShortUrlError::ExhaustedRetries) => {
+            tracing::error!("Exhausted all attempts to generate a unique short code.");
+            HttpResponse::InternalServerError().finish()
+        }
+        Err(InsertShortUrlError::Database(e)) => {
+            tracing::error!("Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Resolving a short URL",
+    skip(connection_pool, req),
+    fields(short_code = %path)
+)]
+pub async fn resolve_short_url(
+    path: web::Path<String>,
+    connection_pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let short_code = path.into_inner();
     match sqlx::query!(
         r#"
-        INSERT INTO short_urls (original_url, short_code, created_at) 
-        VALUES ($1, $2, $3)
+        SELECT original_url FROM short_urls WHERE short_code = $1
         "#,
-        body.url,
-        short_code,
-        Utc::now()
+        short_code
     )
-    .execute(connection_pool.get_ref())
+    .fetch_optional(connection_pool.get_ref())
     .await
     {
-        Ok(_) => HttpResponse::Ok().json(
-            serde_json::json!({ "short_url": format!("{}/{}", settings.domain, short_code) }),
-        ),
+        Ok(Some(record)) => {
+            tracing::info!("Resolved short code to its original URL.");
+            record_click(connection_pool.get_ref().clone(), short_code, &req);
+            HttpResponse::Found()
+                .append_header((header::LOCATION, record.original_url))
+                .finish()
+        }
+        Ok(None) => {
+            tracing::warn!("Short code was not found.");
+            HttpResponse::NotFound().finish()
+        }
         Err(e) => {
-            eprintln!("Failed to execute query: {}", e);
+            tracing::error!("Failed to execute query: {:?}", e);
             HttpResponse::InternalServerError().finish()
         }
     }
 }
 
-async fn generate_short_code(connection_pool: &web::Data<PgPool>) -> String {
-    match sqlx::query!(
-        r#"
-        SELECT id FROM short_urls ORDER BY created_at DESC LIMIT 1
-        "#
-    )
-    .fetch_one(connection_pool.get_ref())
-    .await
-    {
-        Ok(record) => {
-            let id = record.id + 1;
-            let allowed_chars = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-            let base = allowed_chars.len() as u64;
-            let mut num = id as u64;
-            let mut short_code = Vec::new();
-            while num > 0 {
-                let rem = (num % base) as usize;
-                short_code.push(allowed_chars[rem]);
-                num /= base;
-            }
-            String::from_utf8(short_code).unwrap_or_else(|_| "a".to_string())
+/// Records a click without making the redirect wait on it: the insert runs
+/// in a spawned task so a slow or failing write never delays the 302.
+fn record_click(connection_pool: PgPool, short_code: String, req: &HttpRequest) {
+    let referrer = req
+        .headers()
+        .get(header::REFERER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    tokio::spawn(async move {
+        if let Err(e) = sqlx::query!(
+            r#"
+            INSERT INTO clicks (short_code, clicked_at, referrer, user_agent)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            short_code,
+            Utc::now(),
+            referrer,
+            user_agent
+        )
+        .execute(&connection_pool)
+        .await
+        {
+            tracing::error!("Failed to record click: {:?}", e);
         }
-        Err(_) => "a".to_string(), // start from 'a' if no records exist
+    });
+}
+
+const MAX_GENERATION_ATTEMPTS: u8 = 5;
+
+async fn insert_short_url(
+    connection_pool: &PgPool,
+    url: &ValidatedUrl,
+    custom_alias: Option<&ShortCodeAlias>,
+) -> Result<String, InsertShortUrlError> {
+    if let Some(alias) = custom_alias {
+        let short_code = alias.as_ref().to_string();
+        return match sqlx::query!(
+            r#"
+            INSERT INTO short_urls (original_url, short_code, created_at)
+            VALUES ($1, $2, $3)
+            "#,
+            url.as_ref(),
+            short_code,
+            Utc::now()
+        )
+        .execute(connection_pool)
+        .await
+        {
+            Ok(_) => Ok(short_code),
+            Err(e) if is_unique_violation(&e) => Err(InsertShortUrlError::AliasTaken),
+            Err(e) => Err(InsertShortUrlError::Database(e)),
+        };
+    }
+
+    for _ in 0..MAX_GENERATION_ATTEMPTS {
+        // Pull the id from the table's own sequence before inserting, so the
+        // short code can be computed up front. Writing every row through a
+        // shared placeholder (e.g. an empty string, later UPDATEd) would let
+        // the UNIQUE(short_code) constraint serialize concurrent inserts on
+        // that one placeholder value, which defeats the point of letting
+        // Postgres own the identity.
+        let next_id = sqlx::query!(r#"SELECT nextval('short_urls_id_seq') as "id!""#)
+            .fetch_one(connection_pool)
+            .await
+            .map_err(InsertShortUrlError::Database)?
+            .id;
+
+        let short_code = encode_base62(next_id as u64);
+        match sqlx::query!(
+            r#"
+            INSERT INTO short_urls (id, original_url, short_code, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            next_id,
+            url.as_ref(),
+            short_code,
+            Utc::now()
+        )
+        .execute(connection_pool)
+        .await
+        {
+            Ok(_) => return Ok(short_code),
+            Err(e) if is_unique_violation(&e) => continue,
+            Err(e) => return Err(InsertShortUrlError::Database(e)),
+        }
+    }
+
+    Err(InsertShortUrlError::ExhaustedRetries)
+}
+
+fn is_unique_violation(error: &sqlx::Error) -> bool {
+    matches!(
+        error,
+        sqlx::Error::Database(db_error) if db_error.code().as_deref() == Some("23505")
+    )
+}
+
+fn encode_base62(id: u64) -> String {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    if id == 0 {
+        return "a".to_string();
+    }
+    let base = ALPHABET.len() as u64;
+    let mut num = id;
+    let mut digits = Vec::new();
+    while num > 0 {
+        digits.push(ALPHABET[(num % base) as usize]);
+        num /= base;
     }
+    digits.reverse();
+    String::from_utf8(digits).expect("base62 alphabet is ASCII")
 }
@@ -1,8 +1,9 @@
 use actix_web::{App, HttpServer, dev::Server, web};
 use sqlx::PgPool;
 use std::net::TcpListener;
+use tracing_actix_web::TracingLogger;
 
-use crate::routes::{generate_short_url, health_check};
+use crate::routes::{generate_short_url, get_short_url_stats, health_check, resolve_short_url};
 
 pub fn run(
     listener: TcpListener,
@@ -13,8 +14,11 @@ pub fn run(
     let settings = web::Data::new(settings);
     let server = HttpServer::new(move || {
         App::new()
+            .wrap(TracingLogger::default())
             .route("/health-check", web::get().to(health_check))
             .route("/generate", web::post().to(generate_short_url))
+            .route("/{short_code}/stats", web::get().to(get_short_url_stats))
+            .route("/{short_code}", web::get().to(resolve_short_url))
             .app_data(connection.clone())
             .app_data(settings.clone())
     })
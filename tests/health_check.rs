@@ -94,6 +94,162 @@ async fn generate_returns_400_for_invalid_form_data() {
         );
     }
 }
+#[tokio::test]
+async fn resolve_redirects_to_the_original_url_on_a_hit() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let redirecting_client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to build client");
+    let original_url = "https://www.example.com/some/long/url";
+
+    let generate_response = client
+        .post(&format!("{}/generate", app.address))
+        .json(&serde_json::json!({ "url": original_url }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    let short_code = extract_short_code(generate_response).await;
+
+    // Act
+    let response = redirecting_client
+        .get(&format!("{}/{}", app.address, short_code))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 302);
+    assert_eq!(response.headers().get("location").unwrap(), original_url);
+}
+
+#[tokio::test]
+async fn resolve_returns_404_for_an_unknown_short_code() {
+    // Arrange
+    let app = spawn_app().await;
+    let redirecting_client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to build client");
+
+    // Act
+    let response = redirecting_client
+        .get(&format!("{}/does-not-exist", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn stats_reflect_a_recorded_click() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let generate_response = client
+        .post(&format!("{}/generate", app.address))
+        .json(&serde_json::json!({ "url": "https://www.example.com/clicked" }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    let short_code = extract_short_code(generate_response).await;
+
+    client
+        .get(&format!("{}/{}", app.address, short_code))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // Click recording is fire-and-forget, so give the spawned task a moment
+    // to land before asserting on it.
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    // Act
+    let stats_response = client
+        .get(&format!("{}/{}/stats", app.address, short_code))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // Assert
+    assert_eq!(stats_response.status().as_u16(), 200);
+    let stats = stats_response
+        .json::<serde_json::Value>()
+        .await
+        .expect("Failed to parse response JSON");
+    assert_eq!(stats["total_hits"], 1);
+    assert_eq!(stats["daily"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn stats_returns_404_for_an_unknown_short_code() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    // Act
+    let response = client
+        .get(&format!("{}/does-not-exist/stats", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+async fn extract_short_code(response: reqwest::Response) -> String {
+    let short_url = response
+        .json::<serde_json::Value>()
+        .await
+        .expect("Failed to parse response JSON")["short_url"]
+        .as_str()
+        .expect("Response did not contain a short_url")
+        .to_string();
+    short_url
+        .rsplit('/')
+        .next()
+        .expect("short_url had no path segment")
+        .to_string()
+}
+
+#[tokio::test]
+async fn generate_returns_409_when_custom_alias_is_taken() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let first_response = client
+        .post(&format!("{}/generate", app.address))
+        .json(&serde_json::json!({
+            "url": "https://www.example.com/first",
+            "custom_alias": "my-alias"
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(first_response.status().as_u16(), 200);
+
+    // Act
+    let second_response = client
+        .post(&format!("{}/generate", app.address))
+        .json(&serde_json::json!({
+            "url": "https://www.example.com/second",
+            "custom_alias": "my-alias"
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // Assert
+    assert_eq!(second_response.status().as_u16(), 409);
+}
+
 pub struct TestApp {
     pub address: String,
     pub db_pool: PgPool,
@@ -118,14 +274,7 @@ async fn spawn_app() -> TestApp {
 }
 async fn configure_database(config: &DatabaseSettings) -> PgPool {
     // Create database
-    let maintenance_settings = DatabaseSettings {
-        database_name: "postgres".to_string(),
-        username: "postgres".to_string(),
-        password: "password".to_string(),
-        ..config.clone()
-    };
-
-    let mut connection = PgConnection::connect(&maintenance_settings.connection_string())
+    let mut connection = PgConnection::connect(&config.connection_string_without_db())
         .await
         .expect("Failed to connect to Postgres");
 